@@ -0,0 +1,182 @@
+//! Driver for the BP5758D, another 2-wire bit-banged RGBCW driver. Unlike the SM2335/SM2235's
+//! two shared current registers (one for the RGB group, one for the CW group), the BP5758D
+//! programs an independent current limit for each of its 5 outputs.
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{OutputPin, PinState};
+
+use crate::common::{Error, NoDelay, Timings};
+
+pub const BIT_DEPTH: u8 = 10;
+
+// Start address, as documented in Tasmota's BP5758D support.
+const ADDR: u8 = 0b1010_0000;
+
+pub struct Bp5758d<D, C, Dl = NoDelay> {
+    data: D,
+    clk: C,
+    delay: Dl,
+    timings: Timings,
+    channel_currents: [u8; 5],
+}
+
+impl<D, C, E> Bp5758d<D, C, NoDelay>
+where
+    D: OutputPin<Error = E>,
+    C: OutputPin<Error = E>,
+{
+    pub fn init(mut data_pin: D, mut clk_pin: C) -> Result<Self, Error<E>> {
+        data_pin.set_high()?;
+        clk_pin.set_high()?;
+        Ok(Self {
+            data: data_pin,
+            clk: clk_pin,
+            delay: NoDelay,
+            timings: Timings::default(),
+            channel_currents: [0x1F; 5],
+        })
+    }
+}
+
+impl<D, C, Dl, E> Bp5758d<D, C, Dl>
+where
+    D: OutputPin<Error = E>,
+    C: OutputPin<Error = E>,
+    Dl: DelayNs,
+{
+    /// Like [`Self::init`], but additionally takes a [`DelayNs`] implementation and [`Timings`]
+    /// to honor around each clock/data edge. Useful on fast MCUs where back-to-back GPIO writes
+    /// would otherwise violate the datasheet's minimum setup/hold times.
+    pub fn init_with_timing(mut data_pin: D, mut clk_pin: C, delay: Dl, timings: Timings) -> Result<Self, Error<E>> {
+        data_pin.set_high()?;
+        clk_pin.set_high()?;
+        Ok(Self { data: data_pin, clk: clk_pin, delay, timings, channel_currents: [0x1F; 5] })
+    }
+
+    /// Sets the independent 6-bit (0-63) current code for each of the 5 output channels.
+    ///
+    /// Unlike the SM2335/SM2235, which only expose one shared current level per RGB/CW group,
+    /// the BP5758D lets every channel be driven at its own current.
+    pub fn set_channel_currents(&mut self, currents: &[u8; 5]) {
+        self.channel_currents = core::array::from_fn(|i| currents[i] & 0x3F);
+    }
+
+    /// Write the values of all 5 channels to the controller, with each channel value given as a 10-bit integer.
+    ///
+    /// To clarify, the channel values should be in the range `[0, 2^{BIT_DEPTH} = 1024)`). A channel whose
+    /// value is zero is left disabled in the frame's enable byte, mirroring the automatic standby/3-channel/
+    /// 2-channel mode selection in [`crate::sm10bit::Sm10Bit::write`].
+    pub fn write(&mut self, channel_values: &[u16; 5]) -> Result<(), Error<E>> {
+        let msg = build_msg(channel_values, &self.channel_currents);
+        self.write_msg(&msg)
+    }
+
+    fn write_msg(&mut self, msg: &Msg) -> Result<(), Error<E>> {
+        self.data.set_low()?;
+        self.delay.delay_ns(self.timings.data_setup_ns);
+        for byte in msg.0 {
+            for i in (0..8).rev() {
+                let bit = ((byte >> i) & 1) == 1;
+                self.clk.set_low()?;
+                self.delay.delay_ns(self.timings.clk_low_ns);
+                self.data.set_state(PinState::from(bit))?;
+                self.delay.delay_ns(self.timings.data_setup_ns);
+                self.clk.set_high()?;
+                self.delay.delay_ns(self.timings.clk_high_ns);
+            }
+            self.clk.set_low()?;
+            self.delay.delay_ns(self.timings.clk_low_ns);
+            self.data.set_high()?;
+            self.delay.delay_ns(self.timings.data_setup_ns);
+            self.clk.set_high()?;
+            self.delay.delay_ns(self.timings.clk_high_ns);
+        }
+        self.clk.set_low()?;
+        self.delay.delay_ns(self.timings.clk_low_ns);
+        self.clk.set_high()?;
+        self.delay.delay_ns(self.timings.clk_high_ns);
+        self.data.set_high()?;
+        self.delay.delay_ns(self.timings.stop_ns);
+        Ok(())
+    }
+}
+
+/// Builds the 17-byte frame for `channel_values`, enabling exactly the channels that are nonzero
+/// (mirroring the automatic standby/3-channel/2-channel mode selection in
+/// [`crate::sm10bit::Sm10Bit::write`]) and carrying the configured per-channel current codes.
+fn build_msg(channel_values: &[u16; 5], channel_currents: &[u8; 5]) -> Msg {
+    let mut enable = 0u8;
+    for (i, &val) in channel_values.iter().enumerate() {
+        if val != 0 {
+            enable |= 1 << i;
+        }
+    }
+    let mut msg = Msg::zeroed();
+    msg.set_addr(ADDR);
+    msg.set_enable(enable);
+    msg.set_channel_currents(channel_currents);
+    msg.set_channel_values(channel_values);
+    msg
+}
+
+// Frame layout: 1 address byte, 1 enable/mask byte, 5 current-code bytes, then 5 big-endian
+// 10-bit grayscale values (2 bytes each) -- 17 bytes total.
+struct Msg([u8; 17]);
+
+impl Msg {
+    fn zeroed() -> Self {
+        Msg([0; 17])
+    }
+
+    fn set_addr(&mut self, addr: u8) {
+        self.0[0] = addr;
+    }
+
+    fn set_enable(&mut self, enable: u8) {
+        self.0[1] = enable;
+    }
+
+    fn set_channel_currents(&mut self, currents: &[u8; 5]) {
+        self.0[2..7].copy_from_slice(currents);
+    }
+
+    fn set_channel_values(&mut self, vals: &[u16; 5]) {
+        for (i, &val) in vals.iter().enumerate() {
+            self.0[7 + i * 2..][..2].copy_from_slice(val.min((1 << BIT_DEPTH) - 1).to_be_bytes().as_slice());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_msg_sets_addr_and_currents() {
+        let msg = build_msg(&[0; 5], &[1, 2, 3, 4, 5]);
+        assert_eq!(msg.0[0], ADDR);
+        assert_eq!(&msg.0[2..7], &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn build_msg_enable_byte_has_one_bit_per_nonzero_channel() {
+        let msg = build_msg(&[0, 5, 0, 7, 0], &[0; 5]);
+        assert_eq!(msg.0[1], 0b0000_1010);
+
+        let msg = build_msg(&[1, 1, 1, 1, 1], &[0; 5]);
+        assert_eq!(msg.0[1], 0b0001_1111);
+
+        let msg = build_msg(&[0; 5], &[0; 5]);
+        assert_eq!(msg.0[1], 0b0000_0000);
+    }
+
+    #[test]
+    fn build_msg_grayscale_is_big_endian_and_clamped_to_10_bits() {
+        let msg = build_msg(&[0, 1023, 1024, 2000, 512], &[0; 5]);
+        assert_eq!(&msg.0[7..9], &[0x00, 0x00]);
+        assert_eq!(&msg.0[9..11], &[0x03, 0xFF]);
+        assert_eq!(&msg.0[11..13], &[0x03, 0xFF]); // clamped down from 1024
+        assert_eq!(&msg.0[13..15], &[0x03, 0xFF]); // clamped down from 2000
+        assert_eq!(&msg.0[15..17], &[0x02, 0x00]);
+    }
+}
@@ -0,0 +1,49 @@
+//! Protocol building blocks shared by every chip family in this crate: all of them speak some
+//! variant of a 2-wire, clock/data bit-banged protocol, so the error type and timing
+//! configuration are defined once here instead of per-driver.
+
+use embedded_hal::delay::DelayNs;
+
+/// Errors that can occur while driving a chip in this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+    /// Setting or clearing the data or clock pin failed.
+    Pin(E),
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(e: E) -> Self {
+        Error::Pin(e)
+    }
+}
+
+/// Clock/data timings for a bit-banged protocol, in nanoseconds.
+///
+/// The defaults are conservative values derived from the datasheets' setup/hold time diagrams.
+/// On slower MCUs the GPIO toggling itself already takes longer than these, so the delays are
+/// effectively free; on fast cores they prevent the clock/data edges from being shoved together
+/// tightly enough to violate the minimum timings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timings {
+    /// Minimum time between a data edge and the following clock rising edge.
+    pub data_setup_ns: u32,
+    /// Minimum time the clock must stay high.
+    pub clk_high_ns: u32,
+    /// Minimum time the clock must stay low.
+    pub clk_low_ns: u32,
+    /// Minimum time to hold the stop condition before the next transfer.
+    pub stop_ns: u32,
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Self { data_setup_ns: 200, clk_high_ns: 200, clk_low_ns: 200, stop_ns: 200 }
+    }
+}
+
+/// A no-op [`DelayNs`], used when a driver is constructed without an explicit delay.
+pub struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
@@ -0,0 +1,557 @@
+//! Driver for the SM2335/SM2235 family, shared as one generic `Sm10Bit` since ESPHome's
+//! `sm10bit_base` covers both chips with the identical protocol and 12-byte frame.
+
+use core::marker::PhantomData;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{OutputPin, PinState};
+
+use crate::common::{Error, NoDelay, Timings};
+
+// Addressing, as documented in Tasmota xlgt_09_sm2335.ino
+//
+// Select the chip and perform  perform / mode to enter.
+// 0bDD0MMNNN
+//   ^^----------- DD, identification = 11
+//     ^---------- reserved = 0
+//      ^^-------- MM, mode:         standby = 00
+//                       3 channels    (RGB) = 01
+//                       2 channels     (CW) = 10
+//                       5 channels (RGB+CW) = 11
+//        ^^^----- NNN, offset: value 0b000 to 0b100 => start at OUT1 to OUT5
+const ADDR_STANDBY: u8 = 0b11_0_00_000;
+const ADDR_START_3CH: u8 = 0b11_0_01_000;
+const ADDR_START_2CH: u8 = 0b11_0_10_000;
+const ADDR_START_5CH: u8 = 0b11_0_11_000;
+
+pub const BIT_DEPTH: u8 = 10;
+
+/// Default gamma exponent applied in [`Sm10Bit::write_normalized`], matching the curve
+/// ESPHome's light outputs apply for perceptually linear dimming.
+const DEFAULT_GAMMA: f32 = 2.8;
+
+#[cfg(not(feature = "libm"))]
+const GAMMA_LUT_LEN: usize = 256;
+
+/// Float-free approximation of the [`DEFAULT_GAMMA`] curve, as `round((i / 255)^2.8 * 1023)` for
+/// `i` in `0..256`. Used by [`Sm10Bit::write_normalized`] instead of `f32::powf` when the `libm`
+/// feature is disabled; linearly interpolated between entries to recover some of the precision
+/// lost to the 8-bit index.
+#[cfg(not(feature = "libm"))]
+#[rustfmt::skip]
+const GAMMA_2_8_LUT: [u16; GAMMA_LUT_LEN] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1,
+    1, 2, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4,
+    4, 5, 5, 5, 6, 6, 7, 7, 7, 8, 8, 9,
+    10, 10, 11, 11, 12, 13, 13, 14, 15, 15, 16, 17,
+    18, 19, 20, 20, 21, 22, 23, 24, 25, 26, 27, 29,
+    30, 31, 32, 33, 35, 36, 37, 38, 40, 41, 43, 44,
+    46, 47, 49, 50, 52, 54, 55, 57, 59, 61, 63, 64,
+    66, 68, 70, 72, 74, 77, 79, 81, 83, 85, 88, 90,
+    92, 95, 97, 100, 102, 105, 107, 110, 113, 115, 118, 121,
+    124, 127, 130, 133, 136, 139, 142, 145, 149, 152, 155, 158,
+    162, 165, 169, 172, 176, 180, 183, 187, 191, 195, 199, 203,
+    207, 211, 215, 219, 223, 227, 232, 236, 240, 245, 249, 254,
+    258, 263, 268, 273, 277, 282, 287, 292, 297, 302, 308, 313,
+    318, 323, 329, 334, 340, 345, 351, 357, 362, 368, 374, 380,
+    386, 392, 398, 404, 410, 417, 423, 429, 436, 442, 449, 455,
+    462, 469, 476, 483, 490, 497, 504, 511, 518, 525, 533, 540,
+    548, 555, 563, 571, 578, 586, 594, 602, 610, 618, 626, 634,
+    643, 651, 660, 668, 677, 685, 694, 703, 712, 721, 730, 739,
+    748, 757, 766, 776, 785, 795, 804, 814, 824, 833, 843, 853,
+    863, 873, 884, 894, 904, 915, 925, 936, 946, 957, 968, 979,
+    990, 1001, 1012, 1023,
+];
+
+/// Rounds a non-negative float to the nearest integer, without `f32::round` -- which needs
+/// `libm` or `std` and so isn't available in this crate's default, float-free `no_std` build.
+fn round_nonneg(x: f32) -> u16 {
+    (x + 0.5) as u16
+}
+
+/// Linearly interpolate the float-free gamma approximation in [`GAMMA_2_8_LUT`] at `v` (clamped
+/// to `[0.0, 1.0]`), returning a `BIT_DEPTH`-bit value.
+#[cfg(not(feature = "libm"))]
+fn gamma_2_8_lut(v: f32) -> u16 {
+    let scaled = v.clamp(0.0, 1.0) * (GAMMA_LUT_LEN - 1) as f32;
+    let idx = (scaled as usize).min(GAMMA_LUT_LEN - 2);
+    let frac = scaled - idx as f32;
+    let (lo, hi) = (GAMMA_2_8_LUT[idx] as f32, GAMMA_2_8_LUT[idx + 1] as f32);
+    round_nonneg(lo + (hi - lo) * frac)
+}
+
+/// Gamma-correct a normalized `[0.0, 1.0]` channel value into a `BIT_DEPTH`-bit integer, per
+/// `gamma` (see [`Sm10Bit::set_gamma`]). `gamma == 1.0` always takes the direct, float-cheap
+/// linear path; any other value uses `libm::powf` when the `libm` feature is enabled, or else
+/// falls back to the fixed [`GAMMA_2_8_LUT`] approximation of [`DEFAULT_GAMMA`].
+fn gamma_correct(v: f32, gamma: f32) -> u16 {
+    if gamma == 1.0 {
+        return round_nonneg(v.clamp(0.0, 1.0) * ((1u16 << BIT_DEPTH) - 1) as f32);
+    }
+    #[cfg(feature = "libm")]
+    {
+        round_nonneg(libm::powf(v.clamp(0.0, 1.0), gamma) * ((1u16 << BIT_DEPTH) - 1) as f32)
+    }
+    #[cfg(not(feature = "libm"))]
+    {
+        gamma_2_8_lut(v)
+    }
+}
+
+/// A chip in the SM22x5/SM23x5 family of 2-wire bit-banged RGBCW LED drivers.
+///
+/// These chips (as covered by ESPHome's shared `sm10bit_base`) speak the identical protocol and
+/// 12-byte frame; only the interpretation of the 4-bit current-level codes differs between them.
+pub trait ChipVariant {
+    /// Milliamps per RGB group (OUT1-3) current-level code step.
+    const RGB_STEP_MA: u16;
+    /// Milliamps per CW group (OUT4-5) current-level code step.
+    const CW_STEP_MA: u16;
+
+    /// Interpret a 4-bit RGB group current-level code as milliamps, per this chip's datasheet.
+    fn rgb_level_ma(level: u8) -> u16 {
+        (level as u16 & 0xF) * Self::RGB_STEP_MA + Self::RGB_STEP_MA
+    }
+
+    /// Interpret a 4-bit CW group current-level code as milliamps, per this chip's datasheet.
+    fn cw_level_ma(level: u8) -> u16 {
+        (level as u16 & 0xF) * Self::CW_STEP_MA + Self::CW_STEP_MA
+    }
+}
+
+/// Marker for a [`ChipVariant`] whose [`ChipVariant::RGB_STEP_MA`]/[`ChipVariant::CW_STEP_MA`]
+/// table is backed by a datasheet or known-good reference implementation, as opposed to a
+/// provisional estimate.
+///
+/// [`Sm10Bit::set_current_budget`] requires this: deriving a current budget from an unverified
+/// step table would silently base the derating decision on fabricated numbers, defeating the
+/// point of the overcurrent protection it's meant to provide.
+pub trait VerifiedCurrentTable: ChipVariant {}
+
+/// Picks the largest 4-bit current-level code whose per-channel current (per [`ChipVariant`]'s
+/// step table) times `active_channels` still fits under `budget_ma`, or `None` if even the
+/// lowest code (one step) is over budget -- in which case the group must be suppressed entirely
+/// rather than sent at its floor current, which could itself exceed `budget_ma`.
+fn derated_level(active_channels: u16, budget_ma: u16, step_ma: u16) -> Option<u8> {
+    (0..=0xF_u8).rev().find(|&code| ((code as u16) + 1) * step_ma * active_channels.max(1) <= budget_ma)
+}
+
+/// Applies `current_budget` (if any, per [`Sm10Bit::set_current_budget`]) to `channel_values`,
+/// returning the channel values to actually send -- with any group whose budget can't be met
+/// even at the floor current forced to all-zero -- together with the RGB/CW power-level codes to
+/// send for them. With no budget configured, `channel_values` passes through unchanged and the
+/// given `rgb_power_level`/`cw_power_level` are used as-is.
+fn apply_current_budget<V: ChipVariant>(
+    mut channel_values: [u16; 5],
+    current_budget: Option<(u16, u16)>,
+    rgb_power_level: u8,
+    cw_power_level: u8,
+) -> ([u16; 5], u8, u8) {
+    match current_budget {
+        Some((rgb_total_ma, cw_total_ma)) => {
+            let rgb_active = channel_values[..3].iter().filter(|&&v| v != 0).count() as u16;
+            let cw_active = channel_values[3..].iter().filter(|&&v| v != 0).count() as u16;
+            let rgb_level = derated_level(rgb_active, rgb_total_ma, V::RGB_STEP_MA);
+            let cw_level = derated_level(cw_active, cw_total_ma, V::CW_STEP_MA);
+            // Even the floor current would blow the budget for this many active channels --
+            // force the group off rather than send a current that violates it.
+            if rgb_level.is_none() {
+                channel_values[..3].fill(0);
+            }
+            if cw_level.is_none() {
+                channel_values[3..].fill(0);
+            }
+            (channel_values, rgb_level.unwrap_or(0), cw_level.unwrap_or(0))
+        }
+        None => (channel_values, rgb_power_level, cw_power_level),
+    }
+}
+
+/// The SM2335, with RGB group steps of 10mA and CW group steps of 5mA.
+///
+/// | HEX | RGB level | White level | Comment             |
+/// |-----|-----------|-------------|---------------------|
+/// | 0x0 |      10mA |         5mA |                     |
+/// | 0x1 |      20mA |        10mA |                     |
+/// | 0x2 |      30mA |        15mA | Default color value |
+/// | 0x3 |      40mA |        20mA |                     |
+/// | 0x4 |      50mA |        25mA | Default white value |
+/// | 0x5 |      60mA |        30mA |                     |
+/// | 0x6 |      70mA |        35mA |                     |
+/// | 0x7 |      80mA |        40mA |                     |
+/// | 0x8 |      90mA |        45mA |                     |
+/// | 0x9 |     100mA |        50mA |                     |
+/// | 0xA |     110mA |        55mA |                     |
+/// | 0xB |     120mA |        60mA |                     |
+/// | 0xC |     130mA |        65mA |                     |
+/// | 0xD |     140mA |        70mA |                     |
+/// | 0xE |     150mA |        75mA |                     |
+/// | 0xF |     160mA |        80mA |                     |
+pub struct Sm2335;
+
+impl ChipVariant for Sm2335 {
+    const RGB_STEP_MA: u16 = 10;
+    const CW_STEP_MA: u16 = 5;
+}
+
+impl VerifiedCurrentTable for Sm2335 {}
+
+/// The SM2235. Shares the SM2335's protocol and frame layout, but its current-level codes map to
+/// a different (smaller-stepped) current table.
+///
+/// Unlike [`Sm2335`]'s table above, these step sizes are not backed by a datasheet or reference
+/// implementation we have in hand -- they're a provisional estimate based on the SM2235 being
+/// marketed as the lower-current sibling of the SM2335. Treat them as unverified until someone
+/// can confirm against the SM2235 datasheet or a known-good host stack and cite it here.
+///
+/// Because of that, `Sm2235` does not implement [`VerifiedCurrentTable`]: [`Sm2235Egh`] cannot
+/// call [`Sm10Bit::set_current_budget`] (a compile error, not a runtime footgun) until the table
+/// above is confirmed and this impl is added.
+pub struct Sm2235;
+
+impl ChipVariant for Sm2235 {
+    const RGB_STEP_MA: u16 = 8;
+    const CW_STEP_MA: u16 = 4;
+}
+
+pub struct Sm10Bit<V, D, C, Dl = NoDelay> {
+    data: D,
+    clk: C,
+    delay: Dl,
+    timings: Timings,
+    rgb_power_level: u8,
+    cw_power_level: u8,
+    gamma: f32,
+    current_budget: Option<(u16, u16)>,
+    _variant: PhantomData<V>,
+}
+
+/// Driver for the SM2335 RGBCW LED controller.
+pub type Sm2335Egh<D, C, Dl = NoDelay> = Sm10Bit<Sm2335, D, C, Dl>;
+
+/// Driver for the SM2235 RGBCW LED controller.
+pub type Sm2235Egh<D, C, Dl = NoDelay> = Sm10Bit<Sm2235, D, C, Dl>;
+
+impl<V, D, C, E> Sm10Bit<V, D, C, NoDelay>
+where
+    D: OutputPin<Error = E>,
+    C: OutputPin<Error = E>,
+{
+    pub fn init(mut data_pin: D, mut clk_pin: C) -> Result<Self, Error<E>> {
+        data_pin.set_high()?;
+        clk_pin.set_high()?;
+        Ok(Self {
+            data: data_pin,
+            clk: clk_pin,
+            delay: NoDelay,
+            timings: Timings::default(),
+            rgb_power_level: 0x2,
+            cw_power_level: 0x4,
+            gamma: DEFAULT_GAMMA,
+            current_budget: None,
+            _variant: PhantomData,
+        })
+    }
+}
+
+impl<V, D, C, Dl, E> Sm10Bit<V, D, C, Dl>
+where
+    D: OutputPin<Error = E>,
+    C: OutputPin<Error = E>,
+    Dl: DelayNs,
+{
+    /// Like [`Self::init`], but additionally takes a [`DelayNs`] implementation and [`Timings`]
+    /// to honor around each clock/data edge. Useful on fast MCUs where back-to-back GPIO writes
+    /// would otherwise violate the datasheet's minimum setup/hold times.
+    pub fn init_with_timing(mut data_pin: D, mut clk_pin: C, delay: Dl, timings: Timings) -> Result<Self, Error<E>> {
+        data_pin.set_high()?;
+        clk_pin.set_high()?;
+        Ok(Self {
+            data: data_pin,
+            clk: clk_pin,
+            delay,
+            timings,
+            rgb_power_level: 0x2,
+            cw_power_level: 0x4,
+            gamma: DEFAULT_GAMMA,
+            current_budget: None,
+            _variant: PhantomData,
+        })
+    }
+
+    /// Sets the RGB group (OUT1-3) and CW group (OUT4-5) current levels, as 4-bit codes. See
+    /// [`ChipVariant`] for how a given code maps to milliamps on this chip variant.
+    pub fn set_power_levels(&mut self, rgb_level: u8, cw_level: u8) {
+        self.rgb_power_level = rgb_level & 0xF;
+        self.cw_power_level = cw_level & 0xF;
+    }
+
+    /// Sets the gamma exponent applied to each channel in [`Self::write_normalized`], to
+    /// compensate for perceived LED brightness being nonlinear in the raw duty cycle. Defaults
+    /// to [`DEFAULT_GAMMA`] (~2.8); pass `1.0` to disable gamma correction entirely.
+    ///
+    /// Without the `libm` feature enabled (the default), only `1.0` is honored exactly -- any
+    /// other value is silently approximated by a fixed curve for [`DEFAULT_GAMMA`] (~2.8), *not*
+    /// the exponent actually passed in. Enable the `libm` feature to get the exact `gamma` curve
+    /// for arbitrary values.
+    pub fn set_gamma(&mut self, gamma: f32) {
+        self.gamma = gamma;
+    }
+}
+
+impl<V, D, C, Dl, E> Sm10Bit<V, D, C, Dl>
+where
+    V: VerifiedCurrentTable,
+    D: OutputPin<Error = E>,
+    C: OutputPin<Error = E>,
+    Dl: DelayNs,
+{
+    /// Caps the total current drawn by the RGB group (OUT1-3) and the CW group (OUT4-5) to
+    /// `rgb_total_ma`/`cw_total_ma` milliamps, overriding [`Self::set_power_levels`].
+    ///
+    /// On each [`Self::write`], the current level actually sent for a group is the largest code
+    /// whose per-channel current, multiplied by how many channels in that group are nonzero,
+    /// still fits under the budget. If even the lowest current code would exceed the
+    /// budget given how many channels in that group are active, the whole group is forced off
+    /// (its channel values sent as zero) for that write instead of being sent at a current that
+    /// violates the budget -- so a bulb never exceeds its thermal/power budget, even under a
+    /// too-tight budget for the number of channels lit.
+    ///
+    /// Only available for chip variants with a [`VerifiedCurrentTable`] -- currently [`Sm2335`].
+    /// [`Sm2235`]'s current-level table is an unverified estimate (see its docs), so deriving a
+    /// current budget from it would silently base the derating decision on fabricated numbers,
+    /// defeating the point of this protection; that's a compile error here rather than a
+    /// runtime footgun.
+    pub fn set_current_budget(&mut self, rgb_total_ma: u16, cw_total_ma: u16) {
+        self.current_budget = Some((rgb_total_ma, cw_total_ma));
+    }
+}
+
+impl<V, D, C, Dl, E> Sm10Bit<V, D, C, Dl>
+where
+    V: ChipVariant,
+    D: OutputPin<Error = E>,
+    C: OutputPin<Error = E>,
+    Dl: DelayNs,
+{
+    /// The currently configured RGB group current, in milliamps, as interpreted for `V`.
+    pub fn rgb_current_ma(&self) -> u16 {
+        V::rgb_level_ma(self.rgb_power_level)
+    }
+
+    /// The currently configured CW group current, in milliamps, as interpreted for `V`.
+    pub fn cw_current_ma(&self) -> u16 {
+        V::cw_level_ma(self.cw_power_level)
+    }
+}
+
+impl<V, D, C, Dl, E> Sm10Bit<V, D, C, Dl>
+where
+    V: ChipVariant,
+    D: OutputPin<Error = E>,
+    C: OutputPin<Error = E>,
+    Dl: DelayNs,
+{
+    /// Write the values of all 5 channels to the controller, with each channel value given as a normalized float.
+    ///
+    /// Like [`Self::write`], but each channel value is given as a normalized flot in the range `[0.0, 1.0)`.
+    /// Each value is gamma-corrected per [`Self::set_gamma`] before being sent.
+    pub fn write_normalized(&mut self, channel_values: &[f32; 5]) -> Result<(), Error<E>> {
+        let gamma = self.gamma;
+        self.write(&core::array::from_fn(|i| gamma_correct(channel_values[i], gamma)))
+    }
+
+    /// Write the values of all 5 channels to the controller, with each channel value given as a 10-bit integer
+    ///
+    /// To clarify, the channel values should be in the range `[0, 2^{BIT_DEPTH} = 1024)`).
+    /// Unlike [`Self::write_normalized`], this version doesn't use any float ops.
+    ///
+    /// Mainly in order to reduce power usage (I assume that's the reason), there are 4 different "modes" the controller can be in.
+    /// They are
+    /// - standby, with all channels disabled;
+    /// - 3 channel mode with OUT1, OUT2, and OUT3 enabled -- typically the RGB channels;
+    /// - 2 channel mode with OUT4 and OUT5 enabled -- typically the white channels (cool & warm);
+    /// - 5 channel mode with all outputs enabled.
+    /// The different modes will be entered automatically depending on which elements in the argument array are zero.
+    ///
+    /// # Examples
+    /// ```
+    /// # use embedded_hal::digital::OutputPin;
+    /// # fn example<P: OutputPin>(data_pin: P, clock_pin: P) {
+    /// use sm2335egh::Sm2335Egh;
+    /// let mut led_controller = Sm2335Egh::init(data_pin, clock_pin).unwrap();
+    /// // Depending on the board, OUT1 may not necessarily be used to drive the red color channel etc
+    /// let (red, green, blue) = (1023, 0, 800);
+    /// // By leaving OUT4-5 as zero while at least one of OUT1-3 is nonzero, we automatically enter the 3 channel mode.
+    /// led_controller.write(&[blue, red, green, 0, 0]).unwrap();
+    /// # }
+    /// ```
+    pub fn write(&mut self, channel_values: &[u16; 5]) -> Result<(), Error<E>> {
+        let (channel_values, rgb_level, cw_level) = apply_current_budget::<V>(
+            *channel_values,
+            self.current_budget,
+            self.rgb_power_level,
+            self.cw_power_level,
+        );
+        let mut msg = Msg::zeroed();
+        msg.set_channel_values(&channel_values);
+        match channel_values {
+            [0, 0, 0, 0, 0] => {
+                msg.set_addr(ADDR_STANDBY);
+            }
+            [_rgb @ .., 0, 0] => {
+                msg.set_addr(ADDR_START_3CH);
+                msg.set_rgb_power_level(rgb_level);
+            }
+            [0, 0, 0, _cw @ ..] => {
+                msg.set_addr(ADDR_START_2CH);
+                msg.set_cw_power_level(cw_level);
+            }
+            _all => {
+                msg.set_addr(ADDR_START_5CH);
+                msg.set_rgb_power_level(rgb_level);
+                msg.set_cw_power_level(cw_level);
+            }
+        }
+        self.write_msg(&msg)
+    }
+
+    fn write_msg(&mut self, msg: &Msg) -> Result<(), Error<E>> {
+        self.data.set_low()?;
+        self.delay.delay_ns(self.timings.data_setup_ns);
+        for byte in msg.0 {
+            for i in (0..8).rev() {
+                let bit = ((byte >> i) & 1) == 1;
+                self.clk.set_low()?;
+                self.delay.delay_ns(self.timings.clk_low_ns);
+                self.data.set_state(PinState::from(bit))?;
+                self.delay.delay_ns(self.timings.data_setup_ns);
+                self.clk.set_high()?;
+                self.delay.delay_ns(self.timings.clk_high_ns);
+            }
+            self.clk.set_low()?;
+            self.delay.delay_ns(self.timings.clk_low_ns);
+            self.data.set_high()?;
+            self.delay.delay_ns(self.timings.data_setup_ns);
+            self.clk.set_high()?;
+            self.delay.delay_ns(self.timings.clk_high_ns);
+        }
+        self.clk.set_low()?;
+        self.delay.delay_ns(self.timings.clk_low_ns);
+        self.clk.set_high()?;
+        self.delay.delay_ns(self.timings.clk_high_ns);
+        self.data.set_high()?;
+        self.delay.delay_ns(self.timings.stop_ns);
+        Ok(())
+    }
+}
+
+struct Msg([u8; 12]);
+
+impl Msg {
+    fn zeroed() -> Self {
+        Msg([0; 12])
+    }
+
+    fn set_addr(&mut self, addr: u8) {
+        self.0[0] = addr;
+    }
+
+    fn set_rgb_power_level(&mut self, lvl: u8) {
+        self.0[1] = (lvl << 4) | (self.0[1] & 0x0F);
+    }
+
+    fn set_cw_power_level(&mut self, lvl: u8) {
+        self.0[1] = (self.0[1] & 0xF0) | lvl & 0xF;
+    }
+
+    fn set_channel_values(&mut self, vals: &[u16; 5]) {
+        for (i, &val) in vals.iter().enumerate() {
+            self.0[2 + i * 2..][..2].copy_from_slice(val.min((1 << BIT_DEPTH) - 1).to_be_bytes().as_slice());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gamma_lut_covers_full_range() {
+        assert_eq!(gamma_2_8_lut(0.0), 0);
+        assert_eq!(gamma_2_8_lut(1.0), 1023);
+    }
+
+    #[test]
+    fn gamma_lut_is_monotonically_increasing() {
+        let mut prev = 0;
+        for i in 0..=20 {
+            let v = i as f32 / 20.0;
+            let out = gamma_2_8_lut(v);
+            assert!(out >= prev, "gamma_2_8_lut({v}) = {out} should be >= previous {prev}");
+            prev = out;
+        }
+    }
+
+    #[test]
+    fn gamma_correct_disabled_is_linear() {
+        assert_eq!(gamma_correct(0.0, 1.0), 0);
+        assert_eq!(gamma_correct(1.0, 1.0), 1023);
+        assert_eq!(gamma_correct(0.5, 1.0), 512);
+    }
+
+    #[test]
+    fn gamma_correct_default_gamma_matches_lut_fallback() {
+        // With the `libm` feature disabled (the default), any non-1.0 gamma goes through the
+        // fixed GAMMA_2_8_LUT approximation, regardless of the exact value requested.
+        assert_eq!(gamma_correct(0.5, DEFAULT_GAMMA), gamma_2_8_lut(0.5));
+    }
+
+    #[test]
+    fn derated_level_picks_largest_code_under_budget() {
+        // (code + 1) * step_ma * active_channels <= budget_ma
+        assert_eq!(derated_level(1, 160, 10), Some(0xF));
+        assert_eq!(derated_level(3, 30, 10), Some(0x0));
+        assert_eq!(derated_level(2, 60, 10), Some(0x2));
+    }
+
+    #[test]
+    fn derated_level_is_none_when_even_one_step_is_over_budget() {
+        // Even the lowest code (one step) for 2 active channels is already over budget, so the
+        // group must be suppressed entirely rather than sent at a current over the budget.
+        assert_eq!(derated_level(2, 5, 10), None);
+    }
+
+    #[test]
+    fn derated_level_treats_zero_active_channels_like_one() {
+        assert_eq!(derated_level(0, 160, 10), derated_level(1, 160, 10));
+    }
+
+    #[test]
+    fn apply_current_budget_passes_through_unchanged_with_no_budget() {
+        let (values, rgb_level, cw_level) = apply_current_budget::<Sm2335>([1, 2, 3, 4, 5], None, 0x2, 0x4);
+        assert_eq!(values, [1, 2, 3, 4, 5]);
+        assert_eq!((rgb_level, cw_level), (0x2, 0x4));
+    }
+
+    #[test]
+    fn apply_current_budget_derates_within_budget() {
+        // 2 active RGB channels at 10mA/step, 60mA budget -> code 0x2 (30mA/channel).
+        let (values, rgb_level, cw_level) =
+            apply_current_budget::<Sm2335>([100, 100, 0, 200, 0], Some((60, 160)), 0xF, 0xF);
+        assert_eq!(values, [100, 100, 0, 200, 0]);
+        assert_eq!((rgb_level, cw_level), (0x2, 0xF));
+    }
+
+    #[test]
+    fn apply_current_budget_suppresses_group_when_floor_current_is_over_budget() {
+        // 2 active RGB channels, but a 5mA budget can't even fit one 10mA/channel step -- the RGB
+        // group must be forced off (zeroed), not sent at an over-budget floor current.
+        let (values, rgb_level, cw_level) =
+            apply_current_budget::<Sm2335>([100, 100, 0, 200, 0], Some((5, 160)), 0xF, 0xF);
+        assert_eq!(values, [0, 0, 0, 200, 0]);
+        assert_eq!(rgb_level, 0x0);
+        assert_eq!(cw_level, 0xF);
+    }
+}